@@ -0,0 +1,101 @@
+//! A small multi-iteration benchmark runner: repeats a closure, records each
+//! run's duration and token count, and reports min/median/mean and
+//! tokens-per-second instead of a single noisy one-shot timing.
+
+use std::time::Instant;
+
+pub struct IterationResult {
+    pub iteration: usize,
+    pub elapsed_secs: f64,
+    pub tokens: usize,
+}
+
+pub struct BenchmarkSummary {
+    pub name: String,
+    pub iterations: Vec<IterationResult>,
+}
+
+impl BenchmarkSummary {
+    pub fn min_secs(&self) -> f64 {
+        self.iterations
+            .iter()
+            .map(|result| result.elapsed_secs)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn mean_secs(&self) -> f64 {
+        let total: f64 = self.iterations.iter().map(|result| result.elapsed_secs).sum();
+        total / self.iterations.len() as f64
+    }
+
+    pub fn median_secs(&self) -> f64 {
+        let mut elapsed: Vec<f64> = self.iterations.iter().map(|result| result.elapsed_secs).collect();
+        elapsed.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mid = elapsed.len() / 2;
+        if elapsed.len().is_multiple_of(2) {
+            (elapsed[mid - 1] + elapsed[mid]) / 2.0
+        } else {
+            elapsed[mid]
+        }
+    }
+
+    pub fn tokens_per_sec(&self) -> f64 {
+        let tokens = self.iterations.first().map(|result| result.tokens).unwrap_or(0);
+        tokens as f64 / self.mean_secs()
+    }
+
+    pub fn print_human(&self) {
+        println!("    Benchmark: {}", self.name);
+        println!("    Iterations: {}", self.iterations.len());
+        println!("    Min: {:.6}s", self.min_secs());
+        println!("    Median: {:.6}s", self.median_secs());
+        println!("    Mean: {:.6}s", self.mean_secs());
+        println!("    Tokens/sec: {:.0}", self.tokens_per_sec());
+    }
+
+    pub fn to_json(&self) -> String {
+        let iterations_json: Vec<String> = self
+            .iterations
+            .iter()
+            .map(|result| {
+                format!(
+                    r#"{{"iteration":{},"elapsed_secs":{},"tokens":{}}}"#,
+                    result.iteration, result.elapsed_secs, result.tokens
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"name":"{}","min_secs":{},"median_secs":{},"mean_secs":{},"tokens_per_sec":{},"iterations":[{}]}}"#,
+            self.name,
+            self.min_secs(),
+            self.median_secs(),
+            self.mean_secs(),
+            self.tokens_per_sec(),
+            iterations_json.join(",")
+        )
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("name,iteration,elapsed_secs,tokens\n");
+        for result in &self.iterations {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                self.name, result.iteration, result.elapsed_secs, result.tokens
+            ));
+        }
+        csv
+    }
+}
+
+/// Runs `work` `iterations` times, timing each run. `work` returns the
+/// number of tokens it processed, used for the tokens-per-second figure.
+pub fn run_benchmark<F: FnMut() -> usize>(name: &str, iterations: usize, mut work: F) -> BenchmarkSummary {
+    let mut results = Vec::with_capacity(iterations);
+    for iteration in 0..iterations {
+        let start_time = Instant::now();
+        let tokens = work();
+        let elapsed_secs = start_time.elapsed().as_secs_f64();
+        results.push(IterationResult { iteration, elapsed_secs, tokens });
+    }
+    BenchmarkSummary { name: name.to_string(), iterations: results }
+}
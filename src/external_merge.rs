@@ -0,0 +1,203 @@
+//! External-merge counting for corpora too large to hold in memory at once.
+//!
+//! Entries are counted into an in-memory `HashTable` until it crosses a byte
+//! budget, at which point the table is flushed to a sorted run on disk and
+//! counting continues into a fresh table. Once input is exhausted, all runs
+//! are merged with a k-way min-heap merge, summing counts for keys that
+//! appear in more than one run.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::hash_table::HashTable;
+
+pub struct ExternalCounter {
+    table: HashTable<String, u32>,
+    approx_bytes: usize,
+    byte_budget: usize,
+    runs: Vec<PathBuf>,
+    tmp_dir: PathBuf,
+}
+
+impl ExternalCounter {
+    pub fn new(byte_budget: usize) -> io::Result<Self> {
+        let tmp_dir = std::env::temp_dir().join(format!("rust-hash-table-runs-{}", std::process::id()));
+        fs::create_dir_all(&tmp_dir)?;
+        Ok(Self {
+            table: HashTable::new(),
+            approx_bytes: 0,
+            byte_budget,
+            runs: Vec::new(),
+            tmp_dir,
+        })
+    }
+
+    pub fn add_token(&mut self, token: &str) -> io::Result<()> {
+        if !self.table.contains_key(&token.to_string()) {
+            self.approx_bytes += token.len() + std::mem::size_of::<u32>();
+        }
+        *self.table.entry(token.to_string()).or_insert(0) += 1;
+
+        if self.approx_bytes >= self.byte_budget {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the current table out as a run sorted by key, then resets it.
+    fn flush(&mut self) -> io::Result<()> {
+        if self.table.is_empty() {
+            return Ok(());
+        }
+
+        let mut entries: Vec<(String, u32)> = self.table.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let run_path = self.tmp_dir.join(format!("run-{}.bin", self.runs.len()));
+        let mut writer = BufWriter::new(File::create(&run_path)?);
+        for (key, count) in &entries {
+            let key_bytes = key.as_bytes();
+            writer.write_all(&(key_bytes.len() as u32).to_le_bytes())?;
+            writer.write_all(key_bytes)?;
+            writer.write_all(&count.to_le_bytes())?;
+        }
+        writer.flush()?;
+
+        self.runs.push(run_path);
+        self.table = HashTable::new();
+        self.approx_bytes = 0;
+        Ok(())
+    }
+
+    /// Flushes any remaining entries, merges every run, and cleans up the
+    /// temp files. Returns `(word, total_count)` pairs in key order.
+    pub fn finish(mut self) -> io::Result<Vec<(String, u32)>> {
+        self.flush()?;
+        let merged = merge_runs(&self.runs)?;
+        for run in &self.runs {
+            let _ = fs::remove_file(run);
+        }
+        let _ = fs::remove_dir(&self.tmp_dir);
+        Ok(merged)
+    }
+}
+
+fn read_record(reader: &mut BufReader<File>) -> io::Result<Option<(String, u32)>> {
+    let mut len_buf = [0u8; 4];
+    match reader.read_exact(&mut len_buf) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+
+    let len = u32::from_le_bytes(len_buf) as usize;
+    let mut key_buf = vec![0u8; len];
+    reader.read_exact(&mut key_buf)?;
+    let key = String::from_utf8(key_buf).expect("run file contains valid utf8");
+
+    let mut count_buf = [0u8; 4];
+    reader.read_exact(&mut count_buf)?;
+    let count = u32::from_le_bytes(count_buf);
+
+    Ok(Some((key, count)))
+}
+
+struct RunCursor {
+    reader: BufReader<File>,
+    current: Option<(String, u32)>,
+}
+
+impl RunCursor {
+    fn open(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let current = read_record(&mut reader)?;
+        Ok(Self { reader, current })
+    }
+
+    fn advance(&mut self) -> io::Result<()> {
+        self.current = read_record(&mut self.reader)?;
+        Ok(())
+    }
+}
+
+/// One run's head record, ordered by key in reverse so `BinaryHeap` (a
+/// max-heap) behaves as a min-heap.
+struct HeapEntry {
+    key: String,
+    count: u32,
+    run_index: usize,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.cmp(&self.key)
+    }
+}
+
+fn merge_runs(run_paths: &[PathBuf]) -> io::Result<Vec<(String, u32)>> {
+    let mut cursors: Vec<RunCursor> = run_paths
+        .iter()
+        .map(|path| RunCursor::open(path))
+        .collect::<io::Result<_>>()?;
+
+    let mut heap = BinaryHeap::new();
+    for (run_index, cursor) in cursors.iter().enumerate() {
+        if let Some((key, count)) = &cursor.current {
+            heap.push(HeapEntry { key: key.clone(), count: *count, run_index });
+        }
+    }
+
+    let mut merged = Vec::new();
+    while let Some(smallest) = heap.pop() {
+        let key = smallest.key;
+        let mut total = smallest.count;
+
+        cursors[smallest.run_index].advance()?;
+        if let Some((next_key, next_count)) = &cursors[smallest.run_index].current {
+            heap.push(HeapEntry {
+                key: next_key.clone(),
+                count: *next_count,
+                run_index: smallest.run_index,
+            });
+        }
+
+        // Drain every other run whose head record shares this key.
+        while let Some(peek) = heap.peek() {
+            if peek.key != key {
+                break;
+            }
+            let tied = heap.pop().unwrap();
+            total += tied.count;
+
+            cursors[tied.run_index].advance()?;
+            if let Some((next_key, next_count)) = &cursors[tied.run_index].current {
+                heap.push(HeapEntry {
+                    key: next_key.clone(),
+                    count: *next_count,
+                    run_index: tied.run_index,
+                });
+            }
+        }
+
+        merged.push((key, total));
+    }
+
+    Ok(merged)
+}
@@ -0,0 +1,420 @@
+//! A generic linear-probing hash table, reusable beyond word-frequency counting.
+
+pub trait Hashable {
+    /// Returns a byte representation suitable for feeding to a `Hasher`.
+    fn as_hash_bytes(&self) -> Vec<u8>;
+}
+
+impl Hashable for String {
+    fn as_hash_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl Hashable for &str {
+    fn as_hash_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+macro_rules! impl_hashable_int {
+    ($($t:ty),* $(,)?) => {
+        $(impl Hashable for $t {
+            fn as_hash_bytes(&self) -> Vec<u8> {
+                self.to_ne_bytes().to_vec()
+            }
+        })*
+    };
+}
+
+impl_hashable_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl Hashable for f64 {
+    fn as_hash_bytes(&self) -> Vec<u8> {
+        self.to_bits().to_ne_bytes().to_vec()
+    }
+}
+
+/// A pluggable hash function, selected when a `HashTable` is constructed.
+pub trait Hasher {
+    fn hash(&self, bytes: &[u8]) -> usize;
+}
+
+/// djb2: http://www.cse.yorku.ca/~oz/hash.html — fast, but weak and
+/// trivially floodable by an adversary who can choose the keys.
+#[derive(Clone, Default)]
+pub struct Djb2Hasher;
+
+impl Hasher for Djb2Hasher {
+    fn hash(&self, bytes: &[u8]) -> usize {
+        let mut result: usize = 5381;
+        for &b in bytes {
+            result = ((result << 5).wrapping_add(result)).wrapping_add(b as usize);
+        }
+        result
+    }
+}
+
+/// CRC32 (IEEE 802.3 polynomial), computed bit-by-bit rather than via a
+/// lookup table for simplicity.
+#[derive(Clone, Default)]
+pub struct Crc32Hasher;
+
+impl Hasher for Crc32Hasher {
+    fn hash(&self, bytes: &[u8]) -> usize {
+        let mut crc: u32 = 0xFFFF_FFFF;
+        for &byte in bytes {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                let mask = (crc & 1).wrapping_neg();
+                crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+            }
+        }
+        (!crc) as usize
+    }
+}
+
+/// FNV-1a mixed with a per-table random seed, so an attacker can't craft
+/// keys that all collide without already knowing the seed.
+#[derive(Clone)]
+pub struct SeededHasher {
+    seed: u64,
+}
+
+impl SeededHasher {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Picks a fresh seed from `std::collections::hash_map::RandomState`'s
+    /// process-level entropy, so each table gets its own worst-case-resistant seed.
+    pub fn from_entropy() -> Self {
+        use std::collections::hash_map::RandomState;
+        use std::hash::BuildHasher;
+        use std::hash::Hasher as _;
+        Self { seed: RandomState::new().build_hasher().finish() }
+    }
+}
+
+impl Hasher for SeededHasher {
+    fn hash(&self, bytes: &[u8]) -> usize {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325 ^ self.seed;
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0000_0001_0000_01b3);
+        }
+        hash as usize
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Default)]
+enum CellState {
+    #[default]
+    Empty,
+    Occupied,
+    Tombstone,
+}
+
+#[derive(Clone, Default)]
+struct HashCell<K, V> {
+    key: K,
+    value: V,
+    state: CellState,
+}
+
+pub struct HashTable<K, V, H = Djb2Hasher> {
+    cells: Vec<HashCell<K, V>>,
+    taken_count: usize,
+    tombstone_count: usize,
+    hasher: H,
+}
+
+impl<K, V, H> HashTable<K, V, H>
+where
+    K: Hashable + Eq + Clone + Default,
+    V: Default + Clone,
+    H: Hasher + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<K, V, H> HashTable<K, V, H>
+where
+    K: Hashable + Eq + Clone + Default,
+    V: Default + Clone,
+    H: Hasher + Clone,
+{
+    pub fn with_hasher(hasher: H) -> Self {
+        const INIT_CAP: usize = 10;
+        Self {
+            cells: vec![HashCell::default(); INIT_CAP],
+            taken_count: 0,
+            tombstone_count: 0,
+            hasher,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.taken_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.taken_count == 0
+    }
+
+    fn hash_of(&self, key: &K) -> usize {
+        self.hasher.hash(&key.as_hash_bytes())
+    }
+
+    fn extend(&mut self) {
+        assert!(self.cells.len() > 0);
+        let mut new_self = Self {
+            cells: vec![HashCell::default(); self.cells.len() * 2],
+            taken_count: 0,
+            tombstone_count: 0,
+            hasher: self.hasher.clone(),
+        };
+
+        // rehash occupied data from self to new_self; tombstones are dropped
+        for cell in self.cells.iter() {
+            if cell.state == CellState::Occupied {
+                let mut index = new_self.hash_of(&cell.key) % new_self.cells.len();
+
+                while new_self.cells[index].state == CellState::Occupied {
+                    index = (index + 1) % new_self.cells.len();
+                }
+
+                new_self.cells[index].key = cell.key.clone();
+                new_self.cells[index].value = cell.value.clone();
+                new_self.cells[index].state = CellState::Occupied;
+                new_self.taken_count += 1;
+            }
+        }
+        *self = new_self;
+    }
+
+    /// Returns the index of `key`'s occupied cell if present, probing past
+    /// tombstones. Bounded to one full pass over the table so a lookup for a
+    /// missing key can't spin forever on a table with no empty cells left.
+    fn find_index(&self, key: &K) -> Option<usize> {
+        let len = self.cells.len();
+        let start = self.hash_of(key) % len;
+        for step in 0..len {
+            let index = (start + step) % len;
+            match self.cells[index].state {
+                CellState::Occupied if self.cells[index].key == *key => return Some(index),
+                CellState::Empty => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find_index(key).is_some()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.find_index(key).map(|index| &self.cells[index].value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.find_index(key)
+            .map(move |index| &mut self.cells[index].value)
+    }
+
+    /// Inserts `key` with `value`, returning the previous value if it was present.
+    ///
+    /// Reuses the first tombstone seen while probing, so deleted slots don't
+    /// permanently waste space.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.taken_count + self.tombstone_count >= self.cells.len() {
+            self.extend();
+        }
+
+        let mut index = self.hash_of(&key) % self.cells.len();
+        let mut first_tombstone: Option<usize> = None;
+
+        loop {
+            match self.cells[index].state {
+                CellState::Occupied => {
+                    if self.cells[index].key == key {
+                        let old = std::mem::replace(&mut self.cells[index].value, value);
+                        return Some(old);
+                    }
+                }
+                CellState::Tombstone => {
+                    if first_tombstone.is_none() {
+                        first_tombstone = Some(index);
+                    }
+                }
+                CellState::Empty => break,
+            }
+            index = (index + 1) % self.cells.len();
+        }
+
+        let insert_index = match first_tombstone {
+            Some(tombstone_index) => {
+                self.tombstone_count -= 1;
+                tombstone_index
+            }
+            None => index,
+        };
+
+        self.cells[insert_index].key = key;
+        self.cells[insert_index].value = value;
+        self.cells[insert_index].state = CellState::Occupied;
+        self.taken_count += 1;
+        None
+    }
+
+    /// Removes `key`, marking its cell a tombstone so later probes for other
+    /// keys that hashed past it still succeed.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.find_index(key)?;
+        self.cells[index].state = CellState::Tombstone;
+        self.taken_count -= 1;
+        self.tombstone_count += 1;
+        Some(std::mem::replace(&mut self.cells[index].value, V::default()))
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, H> {
+        Entry { table: self, key }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.cells
+            .iter()
+            .filter(|cell| cell.state == CellState::Occupied)
+            .map(|cell| (&cell.key, &cell.value))
+    }
+}
+
+pub struct Entry<'a, K, V, H> {
+    table: &'a mut HashTable<K, V, H>,
+    key: K,
+}
+
+impl<'a, K, V, H> Entry<'a, K, V, H>
+where
+    K: Hashable + Eq + Clone + Default,
+    V: Default + Clone,
+    H: Hasher + Clone,
+{
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        if !self.table.contains_key(&self.key) {
+            self.table.insert(self.key.clone(), default());
+        }
+        self.table.get_mut(&self.key).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of probe steps needed to reach `key`'s cell (0 if it's the
+    /// first cell probed). Reaches into the table's private state directly
+    /// so the test can measure clustering rather than just correctness.
+    fn probe_length<K, V, H>(table: &HashTable<K, V, H>, key: &K) -> usize
+    where
+        K: Hashable + Eq + Clone + Default,
+        V: Default + Clone,
+        H: Hasher + Clone,
+    {
+        let len = table.cells.len();
+        let start = table.hash_of(key) % len;
+        for step in 0..len {
+            let index = (start + step) % len;
+            if table.cells[index].state == CellState::Occupied && table.cells[index].key == *key {
+                return step;
+            }
+        }
+        len
+    }
+
+    #[test]
+    fn seeded_hasher_keeps_adversarial_keys_spread_out() {
+        let capacity = 10;
+        let djb2 = Djb2Hasher::default();
+
+        // Hunt for keys that all land in the same djb2 bucket for a small
+        // table: an adversary who knows the hash can flood a probe chain
+        // this way, since djb2 has no per-table secret to throw them off.
+        let colliding_keys: Vec<String> = (0..10_000)
+            .map(|i| format!("key{}", i))
+            .filter(|key| djb2.hash(&key.as_hash_bytes()) % capacity == 0)
+            .take(8)
+            .collect();
+        assert_eq!(colliding_keys.len(), 8, "expected to find 8 djb2-colliding keys");
+
+        let mut unseeded: HashTable<String, u32, Djb2Hasher> = HashTable::new();
+        for key in &colliding_keys {
+            unseeded.insert(key.clone(), 1);
+        }
+        let unseeded_max_probe = colliding_keys
+            .iter()
+            .map(|key| probe_length(&unseeded, key))
+            .max()
+            .unwrap();
+
+        let mut seeded: HashTable<String, u32, SeededHasher> =
+            HashTable::with_hasher(SeededHasher::new(0x5eed_1234_dead_beef));
+        for key in &colliding_keys {
+            seeded.insert(key.clone(), 1);
+        }
+        let seeded_max_probe = colliding_keys
+            .iter()
+            .map(|key| probe_length(&seeded, key))
+            .max()
+            .unwrap();
+
+        // Every adversarial key piles into djb2's single bucket, so the
+        // worst probe chain grows with every insert; the seeded hasher
+        // spreads the same keys across the table instead.
+        assert_eq!(unseeded_max_probe, colliding_keys.len() - 1);
+        assert!(seeded_max_probe < colliding_keys.len() - 1);
+    }
+
+    #[test]
+    fn removing_a_key_mid_chain_leaves_later_keys_reachable_and_its_slot_reusable() {
+        let capacity = 10;
+        let djb2 = Djb2Hasher::default();
+
+        // Three keys that all hash to the same bucket, so inserting them in
+        // order builds a 3-long probe chain: first, middle, last.
+        let colliding_keys: Vec<String> = (0..10_000)
+            .map(|i| format!("key{}", i))
+            .filter(|key| djb2.hash(&key.as_hash_bytes()) % capacity == 0)
+            .take(3)
+            .collect();
+        assert_eq!(colliding_keys.len(), 3, "expected to find 3 djb2-colliding keys");
+
+        let mut table: HashTable<String, u32> = HashTable::new();
+        for (i, key) in colliding_keys.iter().enumerate() {
+            table.insert(key.clone(), i as u32);
+        }
+        let middle_index = table.find_index(&colliding_keys[1]).unwrap();
+
+        // Remove the middle key, leaving a tombstone between the first and
+        // last keys of the chain.
+        assert_eq!(table.remove(&colliding_keys[1]), Some(1));
+        assert_eq!(table.get(&colliding_keys[1]), None);
+
+        // The last key hashed to the same bucket and probed past the
+        // now-tombstoned slot to find its own; it must still be reachable.
+        assert_eq!(table.get(&colliding_keys[2]), Some(&2));
+
+        // Reinserting a key that hashes into the chain should reuse the
+        // first tombstone seen while probing rather than growing the chain.
+        table.insert(colliding_keys[1].clone(), 99);
+        assert_eq!(table.find_index(&colliding_keys[1]), Some(middle_index));
+        assert_eq!(table.get(&colliding_keys[1]), Some(&99));
+    }
+}
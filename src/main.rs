@@ -3,6 +3,17 @@ use std::fs::File;
 use std::io::{self, Read};
 use std::time::SystemTime;
 
+mod bench;
+mod external_merge;
+mod hash_table;
+mod parallel;
+mod stream;
+
+use bench::run_benchmark;
+use external_merge::ExternalCounter;
+use hash_table::{Crc32Hasher, Djb2Hasher, HashTable, Hasher, SeededHasher};
+use parallel::parallel_word_count;
+
 fn read_entire_file(file_path: &str) -> Result<File, io::Error> {
     let file_res = match File::open(&file_path) {
         Ok(file) => Ok(file),
@@ -53,8 +64,8 @@ impl FreqKVs {
    }
 }
 
-fn naive_analysis(freq: &mut FreqKVs, words: &Vec<&str>) {
-    let start_time = SystemTime::now();
+fn count_naive(words: &Vec<&str>) -> FreqKVs {
+    let mut freq = FreqKVs::new();
     // Linear Search of forming the frequency table
     for token in words {
         if let Some(fkv) = freq.find_key(token) {
@@ -65,6 +76,12 @@ fn naive_analysis(freq: &mut FreqKVs, words: &Vec<&str>) {
             freq.count += 1;
         }
     }
+    freq
+}
+
+fn naive_analysis(freq: &mut FreqKVs, words: &Vec<&str>) {
+    let start_time = SystemTime::now();
+    *freq = count_naive(words);
     let end_time = SystemTime::now();
     let elapsed_time = end_time.duration_since(start_time).unwrap();
 
@@ -77,107 +94,199 @@ fn naive_analysis(freq: &mut FreqKVs, words: &Vec<&str>) {
     println!("    Elapsed time: {:.3}s", elapsed_time.as_secs_f64());
 }
 
-pub trait Hashable {
-    fn hash(&self) -> usize;
+fn count_better<'a>(words: &Vec<&'a str>) -> HashTable<&'a str, u32> {
+    let mut table = HashTable::new();
+    for token in words {
+        *table.entry(*token).or_insert(0) += 1;
+    }
+    table
 }
 
-impl Hashable for String {
-    fn hash(&self) -> usize {
-        // djb2: http://www.cse.yorku.ca/~oz/hash.html
-        let mut result: usize = 5381;
-        for c in self.bytes() {
-            result = ((result << 5).wrapping_add(result)).wrapping_add(c.into());
-        }
-        result
+fn better_analysis<'a, H>(table: &mut HashTable<&'a str, u32, H>, words: &Vec<&'a str>) -> Vec<(&'a str, u32)>
+where
+    H: Hasher + Clone,
+{
+    let start_time = SystemTime::now();
+    for token in words {
+        *table.entry(*token).or_insert(0) += 1;
     }
-}
+    let end_time = SystemTime::now();
+    let elapsed_time = end_time.duration_since(start_time).unwrap();
 
-#[derive(Default, Clone)]
-struct HashCell {
-    key: String, 
-    value: usize,
-    taken: bool,
+    let mut entries: Vec<(&str, u32)> = table.iter().map(|(k, v)| (*k, *v)).collect();
+    println!("    Tokens: {}", entries.len());
+    entries.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    println!("    Top 10 tokens evaluated");
+    for (i, entry) in entries.iter().take(10).enumerate() {
+        println!("      {}: ({}, {})", i, entry.0, entry.1);
+    }
+    println!("    Elapsed time: {:.3}s", elapsed_time.as_secs_f64());
+    entries
 }
 
-struct HashTable {
-    cells: Vec<HashCell>,
-    taken_count: usize,
-}
+fn parallel_analysis<'a>(words: &Vec<&'a str>, threads: usize) -> Vec<(&'a str, u32)> {
+    let (table, elapsed_time) = parallel_word_count(words, threads);
 
-impl HashTable {
-    fn new() -> Self {
-        const INIT_CAP: usize  = 10;
-        Self {
-            cells: vec![HashCell::default(); INIT_CAP],
-            taken_count: 0,
-        }
-    }
-
-    fn extend(&mut self) {
-        assert!(self.cells.len() > 0);
-        let mut new_self = Self {
-            cells: vec![HashCell::default(); self.cells.len()*2],
-            taken_count: 0,
-        };
-        
-        // rehash taken data from self to new_self
-        for cell in self.cells.iter() {
-            if cell.taken {
-                let mut index = cell.key.hash() % new_self.cells.len();
-
-                while new_self.cells[index].taken {
-                    index = (index + 1) % new_self.cells.len();
-                }
-
-                new_self.cells[index].key = cell.key.clone();
-                new_self.cells[index].value = cell.value;
-                new_self.cells[index].taken = true;
-                new_self.taken_count += 1;
-            }
-        }
-        *self = new_self;
+    let mut entries: Vec<(&str, u32)> = table.iter().map(|(k, v)| (*k, *v)).collect();
+    println!("    Threads: {}", threads);
+    println!("    Tokens: {}", entries.len());
+    entries.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    println!("    Top 10 tokens evaluated");
+    for (i, entry) in entries.iter().take(10).enumerate() {
+        println!("      {}: ({}, {})", i, entry.0, entry.1);
     }
+    println!("    Elapsed time: {:.3}s", elapsed_time.as_secs_f64());
+    entries
+}
 
-    fn insert(&mut self, key: &String) {
-        if self.taken_count >= self.cells.len() {
-            self.extend();
-        }
+fn external_analysis(words: &Vec<&str>, byte_budget: usize) -> io::Result<Vec<(String, u32)>> {
+    let start_time = SystemTime::now();
 
-        let mut index = key.hash() % self.cells.len();
+    let mut counter = ExternalCounter::new(byte_budget)?;
+    for token in words {
+        counter.add_token(token)?;
+    }
+    let mut entries = counter.finish()?;
 
-        // linear probing
-        while self.cells[index].taken {
-            if self.cells[index].key == *key {
-                self.cells[index].value += 1;
-                break;
-            }
-            index = (index + 1) % self.cells.len();
-        }
+    let end_time = SystemTime::now();
+    let elapsed_time = end_time.duration_since(start_time).unwrap();
 
-        if !self.cells[index].taken {
-            self.cells[index].key = String::from(key);
-            self.cells[index].value = 1;
-            self.cells[index].taken = true;
-            self.taken_count += 1;
-        }
+    println!("    Spill budget: {} bytes", byte_budget);
+    println!("    Tokens: {}", entries.len());
+    entries.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    println!("    Top 10 tokens evaluated");
+    for (i, entry) in entries.iter().take(10).enumerate() {
+        println!("      {}: ({}, {})", i, entry.0, entry.1);
     }
+    println!("    Elapsed time: {:.3}s", elapsed_time.as_secs_f64());
+    Ok(entries)
 }
 
-fn better_analysis(slots: &mut HashTable, words: &Vec<&str>) {
+fn streaming_analysis(file: File, buffer_size: usize) -> io::Result<Vec<(String, u32)>> {
     let start_time = SystemTime::now();
-    for token in words {
-        slots.insert(&String::from(*token));
-    }
+
+    let table = stream::stream_word_count(file, buffer_size)?;
+    let mut entries: Vec<(String, u32)> = table.iter().map(|(k, v)| (k.clone(), *v)).collect();
+
     let end_time = SystemTime::now();
     let elapsed_time = end_time.duration_since(start_time).unwrap();
 
-    println!("    Tokens: {}", slots.taken_count);
-    slots.cells.sort_by_key(|entry| std::cmp::Reverse(entry.value));
+    println!("    Tokens: {}", entries.len());
+    entries.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
     println!("    Top 10 tokens evaluated");
-    for i in 0..10 {
-        println!("      {}: ({}, {})", i, slots.cells[i].key, slots.cells[i].value);
+    for (i, entry) in entries.iter().take(10).enumerate() {
+        println!("      {}: ({}, {})", i, entry.0, entry.1);
     }
     println!("    Elapsed time: {:.3}s", elapsed_time.as_secs_f64());
+    Ok(entries)
+}
+
+fn parse_threads_arg(args: &[String]) -> usize {
+    args.iter()
+        .position(|arg| arg == "--threads")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        })
+}
+
+fn parse_spill_budget_arg(args: &[String]) -> usize {
+    const DEFAULT_SPILL_BUDGET: usize = 64 * 1024 * 1024;
+    args.iter()
+        .position(|arg| arg == "--spill-budget")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_SPILL_BUDGET)
+}
+
+/// Which `Hasher` the "Better Analysis" table is built with.
+#[derive(Clone, Copy)]
+enum HasherChoice {
+    Djb2,
+    Crc32,
+    Seeded,
+}
+
+impl HasherChoice {
+    fn name(&self) -> &'static str {
+        match self {
+            HasherChoice::Djb2 => "djb2",
+            HasherChoice::Crc32 => "crc32",
+            HasherChoice::Seeded => "seeded",
+        }
+    }
+}
+
+fn parse_hasher_arg(args: &[String]) -> HasherChoice {
+    match args
+        .iter()
+        .position(|arg| arg == "--hasher")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+    {
+        Some("crc32") => HasherChoice::Crc32,
+        Some("seeded") => HasherChoice::Seeded,
+        _ => HasherChoice::Djb2,
+    }
+}
+
+fn parse_iterations_arg(args: &[String]) -> usize {
+    const DEFAULT_ITERATIONS: usize = 10;
+    args.iter()
+        .position(|arg| arg == "--iterations")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|&iterations| iterations > 0)
+        .unwrap_or(DEFAULT_ITERATIONS)
+}
+
+#[derive(Clone, Copy)]
+enum BenchFormat {
+    Human,
+    Json,
+    Csv,
+}
+
+fn parse_format_arg(args: &[String]) -> BenchFormat {
+    match args
+        .iter()
+        .position(|arg| arg == "--format")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+    {
+        Some("json") => BenchFormat::Json,
+        Some("csv") => BenchFormat::Csv,
+        _ => BenchFormat::Human,
+    }
+}
+
+fn run_bench_mode(words: &Vec<&str>, iterations: usize, format: BenchFormat) {
+    let naive_summary = run_benchmark("naive_analysis", iterations, || {
+        std::hint::black_box(count_naive(words));
+        words.len()
+    });
+    let better_summary = run_benchmark("better_analysis", iterations, || {
+        std::hint::black_box(count_better(words));
+        words.len()
+    });
+
+    match format {
+        BenchFormat::Human => {
+            naive_summary.print_human();
+            println!();
+            better_summary.print_human();
+        }
+        BenchFormat::Json => {
+            println!("[{},{}]", naive_summary.to_json(), better_summary.to_json());
+        }
+        BenchFormat::Csv => {
+            print!("{}", naive_summary.to_csv());
+            print!("{}", better_summary.to_csv());
+        }
+    }
 }
 
 fn main() {
@@ -188,29 +297,69 @@ fn main() {
     if args.len() > 1 {
         file_path = String::from(&args[1]);
     } else {
-        println!("Usage: cargo run <file>");
+        println!("Usage: cargo run <file> [--threads N] [--hasher djb2|crc32|seeded] [--bench [--iterations N] [--format json|csv]]");
         println!("ERROR: no input file provided");
     }
 
-    let mut data_file = match read_entire_file(&file_path) {
+    let threads = parse_threads_arg(&args);
+    let spill_budget = parse_spill_budget_arg(&args);
+    let bench_mode = args.iter().any(|arg| arg == "--bench");
+    let iterations = parse_iterations_arg(&args);
+    let format = parse_format_arg(&args);
+    let hasher_choice = parse_hasher_arg(&args);
+
+    let data_file = match read_entire_file(&file_path) {
         Ok(file) => file,
         Err(_) => return,
     };
 
-    let mut content = String::new();
-
-    data_file.read_to_string(&mut content).unwrap();
+    let file_size = data_file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
 
     println!("Analyzing ./{}", file_path);
-    println!("    Size: {} bytes\n", content.len());
+    println!("    Size: {} bytes\n", file_size);
+
+    if file_size >= stream::STREAM_SIZE_THRESHOLD {
+        println!("\"Streaming Analysis\"");
+        streaming_analysis(data_file, stream::DEFAULT_BUFFER_SIZE).unwrap();
+        return;
+    }
+
+    let mut data_file = data_file;
+    let mut content = String::new();
+    data_file.read_to_string(&mut content).unwrap();
     //println!("{}", content);
 
     let words: Vec<&str> = content.trim().split_whitespace().collect();
 
+    if bench_mode {
+        run_bench_mode(&words, iterations, format);
+        return;
+    }
+
     println!("\"Naive Analysis\"");
     naive_analysis(&mut FreqKVs::new(), &words);
     println!();
 
     println!("\"Better Analysis\"");
-    better_analysis(&mut HashTable::new(), &words);
+    println!("    Hasher: {}", hasher_choice.name());
+    let single_threaded_entries = match hasher_choice {
+        HasherChoice::Djb2 => better_analysis(&mut HashTable::<_, _, Djb2Hasher>::new(), &words),
+        HasherChoice::Crc32 => better_analysis(&mut HashTable::with_hasher(Crc32Hasher), &words),
+        HasherChoice::Seeded => {
+            better_analysis(&mut HashTable::with_hasher(SeededHasher::from_entropy()), &words)
+        }
+    };
+    println!();
+
+    println!("\"Parallel Analysis\"");
+    let parallel_entries = parallel_analysis(&words, threads);
+    println!(
+        "    Merged top 10 matches single-threaded result: {}",
+        parallel_entries[..10.min(parallel_entries.len())]
+            == single_threaded_entries[..10.min(single_threaded_entries.len())]
+    );
+    println!();
+
+    println!("\"External Merge Analysis\"");
+    external_analysis(&words, spill_budget).unwrap();
 }
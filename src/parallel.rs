@@ -0,0 +1,72 @@
+//! Multithreaded token counting: shard the input across workers, each building
+//! its own `HashTable`, then merge the per-thread tables into one.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::hash_table::HashTable;
+
+/// Counts tokens using `thread_count` worker threads and reports progress
+/// every `REPORT_INTERVAL` while it runs. Returns the merged table and how
+/// long the whole pass took.
+pub fn parallel_word_count<'a>(
+    words: &[&'a str],
+    thread_count: usize,
+) -> (HashTable<&'a str, u32>, Duration) {
+    const REPORT_INTERVAL: Duration = Duration::from_millis(200);
+
+    let start_time = Instant::now();
+    let thread_count = thread_count.max(1);
+    let chunk_size = words.len().div_ceil(thread_count);
+    let total = words.len();
+
+    let progress = Arc::new(AtomicUsize::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let reporter = {
+        let progress = Arc::clone(&progress);
+        let stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(REPORT_INTERVAL);
+                println!(
+                    "    progress: {} / {} tokens",
+                    progress.load(Ordering::Relaxed),
+                    total
+                );
+            }
+        })
+    };
+
+    let local_tables: Vec<HashTable<&str, u32>> = thread::scope(|scope| {
+        let handles: Vec<_> = words
+            .chunks(chunk_size.max(1))
+            .map(|chunk| {
+                let progress = Arc::clone(&progress);
+                scope.spawn(move || {
+                    let mut local = HashTable::new();
+                    for token in chunk {
+                        *local.entry(*token).or_insert(0) += 1;
+                        progress.fetch_add(1, Ordering::Relaxed);
+                    }
+                    local
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    stop.store(true, Ordering::Relaxed);
+    reporter.join().unwrap();
+
+    let mut merged: HashTable<&str, u32> = HashTable::new();
+    for local in local_tables {
+        for (key, count) in local.iter() {
+            *merged.entry(*key).or_insert(0) += *count;
+        }
+    }
+
+    (merged, start_time.elapsed())
+}
@@ -0,0 +1,68 @@
+//! Incremental tokenizing reader: counts tokens straight off a `File` in
+//! fixed-size buffers instead of materializing the whole file (and every
+//! token slice into it) up front. Peak memory is O(vocabulary) rather than
+//! O(file size), the lazy-loading approach used for large inputs.
+
+use std::fs::File;
+use std::io::{self, Read};
+
+use crate::hash_table::HashTable;
+
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Files at or above this size use the streaming path by default.
+pub const STREAM_SIZE_THRESHOLD: u64 = 10 * 1024 * 1024;
+
+/// Reads `file` in `buffer_size` chunks, splitting on whitespace across
+/// chunk boundaries (a token cut in half is carried into the next chunk),
+/// and counts each token into a fresh `HashTable` as it's produced.
+pub fn stream_word_count(mut file: File, buffer_size: usize) -> io::Result<HashTable<String, u32>> {
+    let mut table = HashTable::new();
+    let mut buffer = vec![0u8; buffer_size];
+    // Raw bytes, not a `String`: a multi-byte UTF-8 char can straddle a
+    // chunk boundary, and decoding each chunk independently would lossily
+    // mangle both halves. Carrying bytes lets us decode once the char (and
+    // the token it's part of) is whole.
+    let mut carry: Vec<u8> = Vec::new();
+
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+
+        carry.extend_from_slice(&buffer[..bytes_read]);
+
+        // Only decode the longest valid UTF-8 prefix; any trailing bytes of
+        // a char split across this boundary stay raw and carry forward.
+        let valid_len = match std::str::from_utf8(&carry) {
+            Ok(_) => carry.len(),
+            Err(e) => e.valid_up_to(),
+        };
+        let text = std::str::from_utf8(&carry[..valid_len])
+            .expect("valid_up_to guarantees a valid UTF-8 prefix");
+
+        // Whichever token is still open at the end of the decoded text (if
+        // any) gets carried whole into the next chunk rather than counted
+        // now, since whitespace may still be coming.
+        let split_at = match text.rfind(char::is_whitespace) {
+            Some(idx) => idx + text[idx..].chars().next().unwrap().len_utf8(),
+            None => 0,
+        };
+
+        for token in text[..split_at].split_whitespace() {
+            *table.entry(token.to_string()).or_insert(0) += 1;
+        }
+
+        let mut next_carry = text[split_at..].as_bytes().to_vec();
+        next_carry.extend_from_slice(&carry[valid_len..]);
+        carry = next_carry;
+    }
+
+    if !carry.is_empty() {
+        let token = String::from_utf8_lossy(&carry).into_owned();
+        *table.entry(token).or_insert(0) += 1;
+    }
+
+    Ok(table)
+}